@@ -0,0 +1,131 @@
+use std::ops::Deref;
+use std::ptr::NonNull;
+use std::sync::atomic::{self, AtomicUsize, Ordering::Acquire, Ordering::Relaxed, Ordering::Release};
+
+struct ArcInner<T> {
+    data: T,
+    rc: AtomicUsize,
+}
+
+// like Rc<T>, but the refcount is an AtomicUsize instead of a Cell<usize>, so it is Send + Sync
+// (as long as T is), and can therefore be shared across threads.
+pub struct Arc<T> {
+    ptr: NonNull<ArcInner<T>>,
+}
+
+// SAFETY: an Arc<T> can be sent across threads as long as it is also safe to send a T across
+// threads, since dropping the last Arc<T> on one thread can drop the T on that thread.
+unsafe impl<T: Send + Sync> Send for Arc<T> {}
+// SAFETY: an Arc<T> can be shared between threads as long as a &T can be, since sharing an
+// Arc<T> is equivalent to sharing a &T (you can dereference to get one).
+unsafe impl<T: Send + Sync> Sync for Arc<T> {}
+
+impl<T> Arc<T> {
+    pub fn new(data: T) -> Self {
+        let boxed = Box::new(ArcInner {
+            data,
+            rc: AtomicUsize::new(1),
+        });
+        Arc {
+            // SAFETY: Box does not give us a null pointer
+            ptr: unsafe { NonNull::new_unchecked(Box::into_raw(boxed)) },
+        }
+    }
+}
+
+impl<T> Clone for Arc<T> {
+    fn clone(&self) -> Self {
+        // Using a Relaxed ordering is alright here, because knowledge of the original reference
+        // is enough to know that the data will still be alive: no action that we take here will
+        // suddenly cause the data to get dropped.
+        let inner = unsafe { self.ptr.as_ref() };
+        let old_rc = inner.rc.fetch_add(1, Relaxed);
+
+        // See std::sync::Arc's implementation for a discussion of why this is needed: without
+        // it, an attacker could theoretically overflow the counter via `mem::forget` and cause a
+        // use-after-free.
+        if old_rc > usize::MAX / 2 {
+            std::process::abort();
+        }
+
+        Arc { ptr: self.ptr }
+    }
+}
+
+impl<T> Deref for Arc<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: self.ptr is only deallocated when the last Arc goes away, and we have an Arc,
+        // so the data is still alive, and will continue to be so as long as we hold self.
+        let inner = unsafe { self.ptr.as_ref() };
+        &inner.data
+    }
+}
+
+impl<T> Drop for Arc<T> {
+    fn drop(&mut self) {
+        // SAFETY: self.ptr is only deallocated when the last Arc goes away, and we haven't
+        // dropped it yet, so the data must still be alive.
+        let inner = unsafe { self.ptr.as_ref() };
+        if inner.rc.fetch_sub(1, Release) != 1 {
+            return;
+        }
+        // This fence is needed to prevent the data access below (and the deallocation) from
+        // being reordered before the refcount decrement above on other threads. Because it is
+        // paired with the Release decrement, the Acquire fence guarantees that all prior
+        // decrements (on any thread) happen-before this point, so we can be certain we really
+        // are the last owner before we free the allocation.
+        atomic::fence(Acquire);
+        // SAFETY: we are the only remaining owner of the data (the refcount just hit 0), and
+        // nobody else can be concurrently accessing the data: any other thread that decremented
+        // the refcount did so before this fence, so any access it made is also ordered before us
+        // freeing the allocation.
+        unsafe {
+            drop(Box::from_raw(self.ptr.as_ptr()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    // Spawns a bunch of threads that concurrently clone and drop the same Arc, and checks that
+    // the inner value is dropped exactly once, after the very last clone goes away. Good to run
+    // under Miri (`cargo +nightly miri test`) to also catch any data-race/aliasing UB.
+    #[test]
+    fn stress_concurrent_clone_and_drop() {
+        struct DetectDrop(Arc<AtomicUsize>);
+        impl Drop for DetectDrop {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let drops = Arc::new(AtomicUsize::new(0));
+        let a = Arc::new(DetectDrop(Arc::clone(&drops)));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let a = Arc::clone(&a);
+                thread::spawn(move || {
+                    for _ in 0..1000 {
+                        let b = Arc::clone(&a);
+                        drop(b);
+                    }
+                    drop(a);
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        drop(a);
+
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+    }
+}
@@ -32,6 +32,41 @@ impl<T> Cell<T> {
         // (because !Sync), and it is executing this function instead.
         unsafe { *self.value.get() }
     }
+
+    pub fn replace(&self, value: T) -> T {
+        // SAFETY: we know no-one else is concurrently mutating self.value (because !Sync)
+        // SAFETY: we know we're not invalidating any references, because we never give any out
+        std::mem::replace(unsafe { &mut *self.value.get() }, value)
+    }
+
+    pub fn take(&self) -> T
+    where
+        T: Default,
+    {
+        self.replace(T::default())
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    pub fn swap(&self, other: &Cell<T>) {
+        if std::ptr::eq(self, other) {
+            return;
+        }
+        // SAFETY: we know no-one else is concurrently mutating either self.value or other.value
+        // (because !Sync), and the pointers are non-overlapping (checked above), so swapping the
+        // values in place is fine.
+        unsafe { std::ptr::swap(self.value.get(), other.value.get()) }
+    }
+
+    pub fn update(&self, f: impl FnOnce(T) -> T)
+    where
+        T: Copy,
+    {
+        let old = self.get();
+        self.set(f(old));
+    }
 }
 
 #[cfg(test)]
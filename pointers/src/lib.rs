@@ -0,0 +1,5 @@
+pub mod arc;
+pub mod cell;
+pub mod rc;
+pub mod refcell;
+pub mod shared;
@@ -1,10 +1,12 @@
 use crate::cell::Cell;
-use std::ptr::NonNull;
 use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ptr::NonNull;
 
 struct RcInner<T> {
-    value: T,
-    refcount: Cell<usize>,
+    value: ManuallyDrop<T>,
+    strong: Cell<usize>,
+    weak: Cell<usize>,
 }
 
 // & is a shared reference, guarantee no exclusive references
@@ -22,33 +24,93 @@ pub struct Rc<T> {
 impl<T> Rc<T> {
     pub fn new(v: T) -> Self {
         let inner = Box::new(RcInner {
-            value: v,
-            refcount: Cell::new(1),
+            value: ManuallyDrop::new(v),
+            // the strong count starts at 1 (us), and so does the weak count: every strong Rc
+            // collectively keeps one implicit Weak alive, so the allocation isn't freed while
+            // any strong Rc could still downgrade from it.
+            strong: Cell::new(1),
+            weak: Cell::new(1),
         });
         // If normal derefence then the box gets dropped when function
         // goes out of scope. Need to not drop the box even though only
         // holding a pointer to it
         Rc {
             // SAFETY: Box does not give us a null pointer
-            inner: unsafe { NonNull::new_unchecked(Box::into_raw(inner))},
+            inner: unsafe { NonNull::new_unchecked(Box::into_raw(inner)) },
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn downgrade(&self) -> Weak<T> {
+        let inner = unsafe { self.inner.as_ref() };
+        let c = inner.weak.get();
+        inner.weak.set(c + 1);
+        Weak {
+            inner: self.inner,
             _marker: PhantomData,
         }
     }
+
+    // a Weak can upgrade into a strong Rc at any time, so strong == 1 alone isn't enough to
+    // prove we're the only handle to the value: a live Weak could upgrade and hand out a &T
+    // while we're still holding the &mut T we return below. Require weak == 1 too (the implicit
+    // weak every strong Rc holds counts here, so this also holds when no Weak has been created).
+    fn is_unique(&self) -> bool {
+        let inner = unsafe { self.inner.as_ref() };
+        inner.strong.get() == 1 && inner.weak.get() == 1
+    }
+
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if self.is_unique() {
+            // SAFETY: we are holding the only strong reference, and no Weak could upgrade into
+            // one either, and we have an exclusive reference to that Rc (via &mut self), so no
+            // one else can be reading or writing through the value.
+            let inner = unsafe { self.inner.as_mut() };
+            Some(&mut inner.value)
+        } else {
+            None
+        }
+    }
+
+    pub fn make_mut(&mut self) -> &mut T
+    where
+        T: Clone,
+    {
+        if !self.is_unique() {
+            // someone else is sharing the allocation with us (another strong Rc, or a Weak that
+            // could still upgrade), so clone the value into a fresh one that only we own.
+            let mut new_rc = Rc::new((*unsafe { self.inner.as_ref() }.value).clone());
+            std::mem::swap(self, &mut new_rc);
+            // `new_rc` now holds the old, shared allocation, with us as one of its strong
+            // owners. Dropping it here relinquishes our share of it exactly like any other
+            // Rc::drop would.
+        }
+        // SAFETY: we just made sure that we are the only strong reference and no Weak could
+        // upgrade into one, either because we already were, or because we just cloned into a
+        // fresh allocation that only we own.
+        let inner = unsafe { self.inner.as_mut() };
+        &mut inner.value
+    }
 }
 impl<T> Clone for Rc<T> {
     fn clone(&self) -> Self {
-       let inner = unsafe { self.inner.as_ref() };
-       let c = inner.refcount.get();
-       inner.refcount.set(c + 1);
-       Rc { inner: self.inner, _marker: PhantomData }
+        let inner = unsafe { self.inner.as_ref() };
+        let c = inner.strong.get();
+        inner.strong.set(c + 1);
+        Rc {
+            inner: self.inner,
+            _marker: PhantomData,
+        }
     }
 }
 
 impl<T> std::ops::Deref for Rc<T> {
     type Target = T;
     fn deref(&self) -> &Self::Target {
-        // SAFETY: self.inner is a Box that is only deallocated when the last Rc goes away
-        // we have an Rc, therefore the Box has not been deallocated, so deref is fine.
+        // SAFETY: self.inner is a Box that is only deallocated when the last Rc (and Weak) goes
+        // away. we have an Rc, therefore the Box has not been deallocated, so deref is fine.
+        // the ManuallyDrop value has also not yet been dropped, since that only happens once the
+        // last strong reference (this one, or an earlier clone) is gone.
         &unsafe { self.inner.as_ref() }.value
     }
 }
@@ -56,16 +118,70 @@ impl<T> std::ops::Deref for Rc<T> {
 impl<T> Drop for Rc<T> {
     fn drop(&mut self) {
         let inner = unsafe { self.inner.as_ref() };
-        let c = inner.refcount.get();
+        let c = inner.strong.get();
         if c == 1 {
-            // need to drop inner before the Box to make invalid.
-            drop(inner);
-            // SAFETY: we are the _only_ Rc left, and we are being dropped.
-            // therefore, after us, there will be no Rc's, and no references to T
+            inner.strong.set(0);
+            // SAFETY: we are the last strong Rc left, and we are being dropped, so after us
+            // there will be no strong Rc's, and no references to the value, so it is safe to
+            // drop the value itself (but not yet the allocation: Weaks may still exist).
+            unsafe {
+                ManuallyDrop::drop(&mut (*self.inner.as_ptr()).value);
+            }
+            // every strong Rc collectively held one implicit Weak; now that the last of them is
+            // gone, let that Weak go too, which frees the allocation if no other Weaks remain.
+            drop(Weak {
+                inner: self.inner,
+                _marker: PhantomData,
+            });
+        } else {
+            inner.strong.set(c - 1);
+        }
+    }
+}
+
+pub struct Weak<T> {
+    inner: NonNull<RcInner<T>>,
+    _marker: PhantomData<RcInner<T>>,
+}
+
+impl<T> Weak<T> {
+    pub fn upgrade(&self) -> Option<Rc<T>> {
+        let inner = unsafe { self.inner.as_ref() };
+        let c = inner.strong.get();
+        if c == 0 {
+            // the value has already been dropped, there is nothing left to upgrade to.
+            return None;
+        }
+        inner.strong.set(c + 1);
+        Some(Rc {
+            inner: self.inner,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<T> Clone for Weak<T> {
+    fn clone(&self) -> Self {
+        let inner = unsafe { self.inner.as_ref() };
+        let c = inner.weak.get();
+        inner.weak.set(c + 1);
+        Weak {
+            inner: self.inner,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Drop for Weak<T> {
+    fn drop(&mut self) {
+        let inner = unsafe { self.inner.as_ref() };
+        let c = inner.weak.get();
+        if c == 1 {
+            // SAFETY: we are the last Weak left, and no strong Rc exists either (it would have
+            // kept at least one Weak alive for itself), so the allocation can be freed.
             let _ = unsafe { Box::from_raw(self.inner.as_ptr()) };
         } else {
-            // there are other Rcs, so don't drop the Box
-            inner.refcount.set(c + 1);
+            inner.weak.set(c - 1);
         }
     }
 }
@@ -74,7 +190,6 @@ impl<T> Drop for Rc<T> {
 mod test {
     // use super::*;
 
-
     // Rust does not know inner owns a T, just that there is a pointer to the T. Does not know that
     // when Rc gets dropped it rust doesn't know there is a T that might get dropped.
     // This matters if T might contain lifetimes (not static).
@@ -0,0 +1,111 @@
+use crate::cell::Cell;
+use std::cell::UnsafeCell;
+
+#[derive(Clone, Copy)]
+enum RefState {
+    Unshared,
+    Shared(usize),
+    Exclusive,
+}
+
+// Like std::cell::RefCell, but built on top of our own Cell for the borrow-state bookkeeping.
+// Unlike Cell, lets you hand out references into the value, at the cost of a runtime check.
+pub struct RefCell<T> {
+    value: UnsafeCell<T>,
+    state: Cell<RefState>,
+}
+
+// implied by UnsafeCell
+// impl<T> !Sync for RefCell<T> {}
+
+impl<T> RefCell<T> {
+    pub fn new(value: T) -> Self {
+        RefCell {
+            value: UnsafeCell::new(value),
+            state: Cell::new(RefState::Unshared),
+        }
+    }
+
+    pub fn borrow(&self) -> Option<Ref<'_, T>> {
+        match self.state.get() {
+            RefState::Unshared => {
+                self.state.set(RefState::Shared(1));
+                Some(Ref { refcell: self })
+            }
+            RefState::Shared(n) => {
+                self.state.set(RefState::Shared(n + 1));
+                Some(Ref { refcell: self })
+            }
+            RefState::Exclusive => None,
+        }
+    }
+
+    pub fn borrow_mut(&self) -> Option<RefMut<'_, T>> {
+        if let RefState::Unshared = self.state.get() {
+            self.state.set(RefState::Exclusive);
+            Some(RefMut { refcell: self })
+        } else {
+            None
+        }
+    }
+}
+
+pub struct Ref<'refcell, T> {
+    refcell: &'refcell RefCell<T>,
+}
+
+impl<T> std::ops::Deref for Ref<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: a Ref is only created if no exclusive reference has been given out.
+        // once it is given out, state is set to Shared, so no exclusive references can be
+        // given out until it is dropped, so dereferencing into a shared reference is fine.
+        unsafe { &*self.refcell.value.get() }
+    }
+}
+
+impl<T> Drop for Ref<'_, T> {
+    fn drop(&mut self) {
+        match self.refcell.state.get() {
+            RefState::Exclusive | RefState::Unshared => unreachable!(),
+            RefState::Shared(1) => {
+                self.refcell.state.set(RefState::Unshared);
+            }
+            RefState::Shared(n) => {
+                self.refcell.state.set(RefState::Shared(n - 1));
+            }
+        }
+    }
+}
+
+pub struct RefMut<'refcell, T> {
+    refcell: &'refcell RefCell<T>,
+}
+
+impl<T> std::ops::Deref for RefMut<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: see DerefMut below.
+        unsafe { &*self.refcell.value.get() }
+    }
+}
+
+impl<T> std::ops::DerefMut for RefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: a RefMut is only created if no other reference has been given out.
+        // once it is given out, state is set to Exclusive, so no future references can be
+        // given out, so we have an exclusive lease on the value until we are dropped.
+        unsafe { &mut *self.refcell.value.get() }
+    }
+}
+
+impl<T> Drop for RefMut<'_, T> {
+    fn drop(&mut self) {
+        match self.refcell.state.get() {
+            RefState::Shared(_) | RefState::Unshared => unreachable!(),
+            RefState::Exclusive => {
+                self.refcell.state.set(RefState::Unshared);
+            }
+        }
+    }
+}
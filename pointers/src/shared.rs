@@ -0,0 +1,150 @@
+use crate::cell::Cell;
+use std::cell::UnsafeCell;
+use std::mem::ManuallyDrop;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+struct SharedInner<T> {
+    // wrapped in ManuallyDrop so that `take` can move the value out without requiring `T: Default`
+    // to leave a placeholder behind; `taken` then records that it has already been moved out.
+    value: UnsafeCell<ManuallyDrop<T>>,
+    count: Cell<usize>,
+    // 0 means no one is borrowing the value, a positive number is how many shared borrows are
+    // outstanding, and -1 means there is a single exclusive borrow outstanding.
+    access: Cell<isize>,
+    taken: Cell<bool>,
+}
+
+// A handle that is both cheaply cloneable, like Rc, and interior-mutable via runtime-checked
+// borrows, like RefCell, fused into a single allocation. Useful for graph-shaped data (e.g. a
+// tree with parent pointers) where every node needs to be both shared and mutable.
+pub struct Shared<T> {
+    inner: NonNull<SharedInner<T>>,
+}
+
+impl<T> Shared<T> {
+    pub fn new(value: T) -> Self {
+        let inner = Box::new(SharedInner {
+            value: UnsafeCell::new(ManuallyDrop::new(value)),
+            count: Cell::new(1),
+            access: Cell::new(0),
+            taken: Cell::new(false),
+        });
+        Shared {
+            // SAFETY: Box does not give us a null pointer
+            inner: unsafe { NonNull::new_unchecked(Box::into_raw(inner)) },
+        }
+    }
+
+    pub fn borrow_ref(&self) -> Option<Ref<'_, T>> {
+        let inner = unsafe { self.inner.as_ref() };
+        if inner.taken.get() {
+            return None;
+        }
+        let access = inner.access.get();
+        if access < 0 {
+            return None;
+        }
+        inner.access.set(access + 1);
+        Some(Ref { shared: self })
+    }
+
+    pub fn borrow_mut(&self) -> Option<RefMut<'_, T>> {
+        let inner = unsafe { self.inner.as_ref() };
+        if inner.taken.get() || inner.access.get() != 0 {
+            return None;
+        }
+        inner.access.set(-1);
+        Some(RefMut { shared: self })
+    }
+
+    pub fn take(&self) -> Option<T> {
+        let guard = self.borrow_mut()?;
+        let inner = unsafe { self.inner.as_ref() };
+        // SAFETY: `guard` being exclusive means no other Ref/RefMut can be reading or writing
+        // through the value right now, and `taken` being false (checked by borrow_mut above)
+        // means no one has moved it out before. We set `taken` before dropping `guard`, so the
+        // slot is marked moved-out before any future borrow could observe it again.
+        let value = unsafe { ManuallyDrop::take(&mut *inner.value.get()) };
+        inner.taken.set(true);
+        drop(guard);
+        Some(value)
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        let inner = unsafe { self.inner.as_ref() };
+        let c = inner.count.get();
+        inner.count.set(c + 1);
+        Shared { inner: self.inner }
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let inner = unsafe { self.inner.as_ref() };
+        let c = inner.count.get();
+        if c == 1 {
+            if !inner.taken.get() {
+                // SAFETY: we are the last Shared left, so no references to the value can be
+                // outstanding, and the value has not already been moved out by `take`.
+                unsafe { ManuallyDrop::drop(&mut *inner.value.get()) };
+            }
+            // SAFETY: we are the last Shared left, and we are being dropped, so after us there
+            // will be no handles left, and no references to the value, so it is safe to free the
+            // allocation.
+            let _ = unsafe { Box::from_raw(self.inner.as_ptr()) };
+        } else {
+            inner.count.set(c - 1);
+        }
+    }
+}
+
+pub struct Ref<'shared, T> {
+    shared: &'shared Shared<T>,
+}
+
+impl<T> Deref for Ref<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: a Ref is only handed out while access is non-negative, and access is only set
+        // negative for the lifetime of a RefMut, so no exclusive reference can exist at the same
+        // time as this one.
+        unsafe { &*self.shared.inner.as_ref().value.get() }
+    }
+}
+
+impl<T> Drop for Ref<'_, T> {
+    fn drop(&mut self) {
+        let inner = unsafe { self.shared.inner.as_ref() };
+        inner.access.set(inner.access.get() - 1);
+    }
+}
+
+pub struct RefMut<'shared, T> {
+    shared: &'shared Shared<T>,
+}
+
+impl<T> Deref for RefMut<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: see DerefMut below.
+        unsafe { &*self.shared.inner.as_ref().value.get() }
+    }
+}
+
+impl<T> DerefMut for RefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: a RefMut is only handed out while access is 0, and access is set to -1 for as
+        // long as this RefMut lives, so no other Ref or RefMut can exist at the same time.
+        unsafe { &mut *self.shared.inner.as_ref().value.get() }
+    }
+}
+
+impl<T> Drop for RefMut<'_, T> {
+    fn drop(&mut self) {
+        let inner = unsafe { self.shared.inner.as_ref() };
+        inner.access.set(0);
+    }
+}